@@ -1,11 +1,10 @@
 pub mod math;
 
 pub mod material;
+pub mod texture;
 
-mod camera;
 mod object;
 mod scene;
 
-pub use camera::Camera;
 pub use object::Object;
-pub use scene::Scene;
+pub use scene::{Camera, Scene};
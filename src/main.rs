@@ -4,7 +4,8 @@ use ray_tracer::{
 	material::{Lambertian, Refractive, Specular},
 	math::{Curve, Vec3},
 	object::Sphere,
-	scene::{Camera, Scene}
+	scene::{Camera, Scene},
+	texture::SolidColor
 };
 
 use std::{
@@ -26,7 +27,7 @@ fn generate_wide_angle(width: u32, height: u32) -> (Scene, Camera) {
 		radius: radius,
 
 		material: Arc::new(Lambertian {
-			albedo: Vec3(0.0, 0.0, 1.0)
+			albedo: Arc::new(SolidColor(Vec3(0.0, 0.0, 1.0)))
 		})
 	});
 
@@ -36,7 +37,7 @@ fn generate_wide_angle(width: u32, height: u32) -> (Scene, Camera) {
 		radius: radius,
 
 		material: Arc::new(Lambertian {
-			albedo: Vec3(1.0, 0.0, 0.0)
+			albedo: Arc::new(SolidColor(Vec3(1.0, 0.0, 0.0)))
 		})
 	});
 
@@ -68,7 +69,7 @@ fn generate_cover(width: u32, height: u32) -> (Scene, Camera) {
 		radius: 1000.0,
 
 		material: Arc::new(Lambertian {
-			albedo: Vec3(0.5, 0.5, 0.5)
+			albedo: Arc::new(SolidColor(Vec3(0.5, 0.5, 0.5)))
 		})
 	});
 
@@ -78,7 +79,7 @@ fn generate_cover(width: u32, height: u32) -> (Scene, Camera) {
 		radius: 1.0,
 
 		material: Arc::new(Lambertian {
-			albedo: Vec3(0.4, 0.2, 0.1)
+			albedo: Arc::new(SolidColor(Vec3(0.4, 0.2, 0.1)))
 		})
 	});
 
@@ -88,7 +89,7 @@ fn generate_cover(width: u32, height: u32) -> (Scene, Camera) {
 		radius: 1.0,
 
 		material: Arc::new(Refractive {
-			albedo: Vec3(1.0, 1.0, 1.0),
+			albedo: Arc::new(SolidColor(Vec3(1.0, 1.0, 1.0))),
 			index: 1.5
 		})
 	});
@@ -99,7 +100,7 @@ fn generate_cover(width: u32, height: u32) -> (Scene, Camera) {
 		radius: 1.0,
 
 		material: Arc::new(Specular {
-			albedo: Vec3(0.7, 0.6, 0.5),
+			albedo: Arc::new(SolidColor(Vec3(0.7, 0.6, 0.5))),
 			fuzziness: 0.0
 		})
 	});
@@ -108,7 +109,7 @@ fn generate_cover(width: u32, height: u32) -> (Scene, Camera) {
 	let mut rng = rand::thread_rng();
 
 	let refractive = Arc::new(Refractive {
-		albedo: Vec3(1.0, 1.0, 1.0),
+		albedo: Arc::new(SolidColor(Vec3(1.0, 1.0, 1.0))),
 		index: 1.5
 	});
 
@@ -131,7 +132,8 @@ fn generate_cover(width: u32, height: u32) -> (Scene, Camera) {
 						radius: 0.2,
 
 						material: Arc::new(Lambertian {
-							albedo: Vec3::random_in_unit_cube() * Vec3::random_in_unit_cube()
+							albedo: Arc::new(SolidColor(
+								Vec3::random_in_unit_cube(&mut rng) * Vec3::random_in_unit_cube(&mut rng)))
 						})
 					}
 				} else if choose_mat < 0.95 {
@@ -140,7 +142,7 @@ fn generate_cover(width: u32, height: u32) -> (Scene, Camera) {
 						radius: 0.2,
 
 						material: Arc::new(Specular {
-							albedo: Vec3::random_in_cube(0.5, 1.0),
+							albedo: Arc::new(SolidColor(Vec3::random_in_cube(&mut rng, 0.5, 1.0))),
 							fuzziness: rng.gen_range(0.0, 0.5)
 						})
 					}
@@ -181,7 +183,7 @@ fn main() {
 
 	let (scene, camera) = generate_cover(WIDTH, HEIGHT);
 
-	for color in scene.render(WIDTH, HEIGHT, camera, 0.001, std::f64::INFINITY, 75, 10) {
+	for color in scene.render(WIDTH, HEIGHT, camera, 0.001, std::f64::INFINITY, 75, 10, 0) {
 		// Gamma correction (1 / 2)
 		data.push((color.0.sqrt() * 256.0).min(255.0) as u8);
 		data.push((color.1.sqrt() * 256.0).min(255.0) as u8);
@@ -1,51 +1,105 @@
 use crate::math::Vec3;
 use crate::object::{Hit, Face};
+use crate::texture::Texture;
+
+use rand::{Rng, RngCore};
+
+use std::sync::Arc;
 
 pub trait Material: Send + Sync {
 	// Returns a redirection record if the ray wasn't absorbed
-	fn redirect(&self, hit: &Hit) -> Option<Redirection>;
+	fn redirect(&self, hit: &Hit, rng: &mut dyn RngCore) -> Option<Redirection>;
+
+	/// The probability density (w.r.t. solid angle) of scattering toward "direction"; used to
+	/// re-weight samples drawn from something other than this material's own distribution (e.g.
+	/// a light), so it only needs to be meaningful for non-specular materials
+	fn pdf(&self, _hit: &Hit, _direction: Vec3) -> f64 {
+		1.0
+	}
+
+	/// Light emitted by the surface itself, independent of any incoming ray
+	fn emitted(&self, _hit: &Hit) -> Vec3 {
+		Vec3(0.0, 0.0, 0.0)
+	}
+
+	/// Whether this material is left out of light importance sampling, either because it scatters
+	/// along a (near-)deterministic direction or because its "pdf" isn't a real density
+	fn is_specular(&self) -> bool {
+		false
+	}
 }
 
 pub struct Redirection {
 	pub direction: Vec3,
-	pub albedo: Vec3
+	pub albedo: Vec3,
+	pub pdf: f64
 }
 
 pub struct Lambertian {
-	pub albedo: Vec3
+	pub albedo: Arc<dyn Texture>
 }
 
 impl Material for Lambertian {
-	fn redirect(&self, hit: &Hit) -> Option<Redirection> {
+	fn redirect(&self, hit: &Hit, rng: &mut dyn RngCore) -> Option<Redirection> {
+		let direction = Vec3::random_cosine_direction(rng).from_basis(hit.normal).unit();
+		let pdf = self.pdf(hit, direction);
+
 		Some(Redirection {
-			direction: (hit.normal + Vec3::random_on_unit_sphere()).unit(),
-			albedo: self.albedo
+			direction: direction,
+			albedo: self.albedo.value(hit.u, hit.v, hit.point),
+			pdf: pdf
 		})
 	}
+
+	fn pdf(&self, hit: &Hit, direction: Vec3) -> f64 {
+		(direction.unit().dot(hit.normal) / std::f64::consts::PI).max(0.0)
+	}
+}
+
+pub struct DiffuseLight {
+	pub albedo: Arc<dyn Texture>
+}
+
+impl Material for DiffuseLight {
+	// Lights absorb every ray that hits them; they only contribute via "emitted"
+	fn redirect(&self, _hit: &Hit, _rng: &mut dyn RngCore) -> Option<Redirection> {
+		None
+	}
+
+	fn emitted(&self, hit: &Hit) -> Vec3 {
+		self.albedo.value(hit.u, hit.v, hit.point)
+	}
 }
 
 pub struct Spherical {
-	pub albedo: Vec3
+	pub albedo: Arc<dyn Texture>
 }
 
 impl Material for Spherical {
-	fn redirect(&self, hit: &Hit) -> Option<Redirection> {
+	fn redirect(&self, hit: &Hit, rng: &mut dyn RngCore) -> Option<Redirection> {
 		Some(Redirection {
-			direction: (hit.normal + Vec3::random_in_unit_sphere()),
-			albedo: self.albedo
+			direction: (hit.normal + Vec3::random_in_unit_sphere(rng)),
+			albedo: self.albedo.value(hit.u, hit.v, hit.point),
+			pdf: 1.0
 		})
 	}
+
+	// Its "pdf" of 1.0 is a placeholder, not a real density, so it can't be mixed with light
+	// importance sampling
+	fn is_specular(&self) -> bool {
+		true
+	}
 }
 
 pub struct Hemispherical {
-	pub albedo: Vec3
+	pub albedo: Arc<dyn Texture>
 }
 
 impl Material for Hemispherical {
-	fn redirect(&self, hit: &Hit) -> Option<Redirection> {
+	fn redirect(&self, hit: &Hit, rng: &mut dyn RngCore) -> Option<Redirection> {
 		Some(Redirection {
 			direction: {
-				let direction = Vec3::random_in_unit_sphere();
+				let direction = Vec3::random_in_unit_sphere(rng);
 
 				(hit.normal + if direction.dot(hit.normal) >= 0.0 {
 					direction
@@ -54,13 +108,20 @@ impl Material for Hemispherical {
 				}).unit()
 			},
 
-			albedo: self.albedo
+			albedo: self.albedo.value(hit.u, hit.v, hit.point),
+			pdf: 1.0
 		})
 	}
+
+	// Its "pdf" of 1.0 is a placeholder, not a real density, so it can't be mixed with light
+	// importance sampling
+	fn is_specular(&self) -> bool {
+		true
+	}
 }
 
 pub struct Specular {
-	pub albedo: Vec3,
+	pub albedo: Arc<dyn Texture>,
 	pub fuzziness: f64
 }
 
@@ -72,22 +133,27 @@ impl Specular {
 }
 
 impl Material for Specular {
-	fn redirect(&self, hit: &Hit) -> Option<Redirection> {
-		let direction = (Self::reflect(hit) + Vec3::random_in_unit_sphere() * self.fuzziness).unit();
+	fn redirect(&self, hit: &Hit, rng: &mut dyn RngCore) -> Option<Redirection> {
+		let direction = (Self::reflect(hit) + Vec3::random_in_unit_sphere(rng) * self.fuzziness).unit();
 
 		if direction.dot(hit.normal) > 0.0 {
 			Some(Redirection {
 				direction: direction,
-				albedo: self.albedo
+				albedo: self.albedo.value(hit.u, hit.v, hit.point),
+				pdf: 1.0
 			})
 		} else {
 			None
 		}
 	}
+
+	fn is_specular(&self) -> bool {
+		true
+	}
 }
 
 pub struct Refractive {
-	pub albedo: Vec3,
+	pub albedo: Arc<dyn Texture>,
 	pub index: f64
 }
 
@@ -100,7 +166,7 @@ impl Refractive {
 }
 
 impl Material for Refractive {
-	fn redirect(&self, hit: &Hit) -> Option<Redirection> {
+	fn redirect(&self, hit: &Hit, rng: &mut dyn RngCore) -> Option<Redirection> {
 		let cos = -hit.ray.direction.dot(hit.normal);
 
 		let sin_ratio = match hit.face {
@@ -111,14 +177,19 @@ impl Material for Refractive {
 		let cos_ratio = (1.0 - sin_ratio * sin_ratio * (1.0 - cos * cos)).sqrt();
 
 		Some(Redirection {
-			direction: if cos_ratio.is_nan() || rand::random::<f64>() < self.schlick(cos) {
+			direction: if cos_ratio.is_nan() || rng.gen::<f64>() < self.schlick(cos) {
 				Specular::reflect(hit)
 			} else {
 				(hit.ray.direction + hit.normal * cos) * sin_ratio - hit.normal * cos_ratio
 			},
 
-			albedo: self.albedo
+			albedo: self.albedo.value(hit.u, hit.v, hit.point),
+			pdf: 1.0
 		})
 
 	}
+
+	fn is_specular(&self) -> bool {
+		true
+	}
 }
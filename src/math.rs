@@ -28,22 +28,17 @@ impl Vec3 {
 		self / self.len()
 	}
 
-	pub fn random_in_cube(min: f64, max: f64) -> Self {
-		let mut rng = rand::thread_rng();
-
+	pub fn random_in_cube(rng: &mut (impl Rng + ?Sized), min: f64, max: f64) -> Self {
 		Vec3(rng.gen_range(min, max),
 		     rng.gen_range(min, max),
 		     rng.gen_range(min, max))
 	}
 
-	pub fn random_in_unit_cube() -> Self {
-		let mut rng = rand::thread_rng();
+	pub fn random_in_unit_cube(rng: &mut (impl Rng + ?Sized)) -> Self {
 		Vec3(rng.gen(), rng.gen(), rng.gen())
 	}
 
-	pub fn random_on_unit_sphere() -> Self {
-		let mut rng = rand::thread_rng();
-
+	pub fn random_on_unit_sphere(rng: &mut (impl Rng + ?Sized)) -> Self {
 		let a = rng.gen_range(0.0, std::f64::consts::PI * 2.0);
 		let b = rng.gen::<f64>();
 		let c = (1.0 - b * b).sqrt();
@@ -51,18 +46,44 @@ impl Vec3 {
 		Vec3(c * a.cos(), c * a.sin(), b)
 	}
 
-	pub fn random_in_unit_sphere() -> Self {
-		Self::random_on_unit_sphere() * rand::random::<f64>().powf(1.0 / 3.0)
+	pub fn random_in_unit_sphere(rng: &mut (impl Rng + ?Sized)) -> Self {
+		Self::random_on_unit_sphere(rng) * rng.gen::<f64>().powf(1.0 / 3.0)
 	}
 
-	pub fn random_in_unit_disk() -> Self {
-		let mut rng = rand::thread_rng();
-
+	pub fn random_in_unit_disk(rng: &mut (impl Rng + ?Sized)) -> Self {
 		let angle = rng.gen_range(0.0, std::f64::consts::PI * 2.0);
 		let radius = rng.gen::<f64>().sqrt();
 
 		Vec3(angle.cos(), angle.sin(), 0.0) * radius
 	}
+
+	/// Samples a direction whose z component is cosine-weighted toward 1 (i.e. "up"), for use
+	/// with `from_basis` when sampling a cosine-weighted hemisphere around a normal
+	pub fn random_cosine_direction(rng: &mut (impl Rng + ?Sized)) -> Self {
+		let r1 = rng.gen::<f64>();
+		let r2 = rng.gen::<f64>();
+		let z = (1.0 - r2).sqrt();
+
+		let phi = r1 * std::f64::consts::PI * 2.0;
+		let radius = r2.sqrt();
+
+		Vec3(phi.cos() * radius, phi.sin() * radius, z)
+	}
+
+	/// Treats "self" as a vector in the local coordinate system of an orthonormal basis whose
+	/// "w" axis is "normal", and returns the corresponding world-space vector
+	pub fn from_basis(self, normal: Self) -> Self {
+		let a = if normal.0.abs() > 0.9 {
+			Vec3(0.0, 1.0, 0.0)
+		} else {
+			Vec3(1.0, 0.0, 0.0)
+		};
+
+		let tangent = normal.cross(a).unit();
+		let bitangent = normal.cross(tangent);
+
+		tangent * self.0 + bitangent * self.1 + normal * self.2
+	}
 }
 
 impl Add for Vec3 {
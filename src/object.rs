@@ -3,11 +3,118 @@ use crate::{
 	math::{Aabb, Curve, Ray, Vec3}
 };
 
+use rand::{Rng, RngCore};
+
 use std::sync::Arc;
 
 pub trait Object: Send + Sync {
 	fn aabb(&self, time0: f64, time1: f64) -> Aabb;
 	fn hit(&self, ray: Ray, min_distance: f64, max_distance: f64) -> Option<Hit>;
+
+	/// The probability density (w.r.t. solid angle) that a ray from "origin" toward "direction"
+	/// hits this object; used only for light importance sampling, so objects that are never
+	/// registered as lights can rely on the default
+	fn pdf_value(&self, _origin: Vec3, _direction: Vec3) -> f64 {
+		0.0
+	}
+
+	/// Samples a direction from "origin" that's guaranteed to hit this object; used only for
+	/// light importance sampling. The default, for objects never registered as lights, returns a
+	/// fixed direction that isn't aimed at the object
+	fn random_toward(&self, _origin: Vec3, _rng: &mut dyn RngCore) -> Vec3 {
+		Vec3(1.0, 0.0, 0.0)
+	}
+}
+
+/// A bounding volume hierarchy node, splitting a set of objects into two halves along a random
+/// axis so that `hit` can skip entire subtrees whose AABB the ray misses
+pub struct BvhNode {
+	left: Arc<dyn Object>,
+	right: Arc<dyn Object>,
+	aabb: Aabb
+}
+
+impl BvhNode {
+	pub fn new(mut objects: Vec<Arc<dyn Object>>, time0: f64, time1: f64, rng: &mut impl Rng) -> Self {
+		if objects.is_empty() {
+			let empty: Arc<dyn Object> = Arc::new(EmptyObject);
+
+			return Self { left: empty.clone(), right: empty, aabb: EmptyObject.aabb(time0, time1) };
+		}
+
+		let axis = rng.gen_range(0, 3) as u8;
+
+		objects.sort_by(|a, b| {
+			a.aabb(time0, time1).min[axis]
+				.partial_cmp(&b.aabb(time0, time1).min[axis])
+				.unwrap()
+		});
+
+		let (left, right): (Arc<dyn Object>, Arc<dyn Object>) = match objects.len() {
+			1 => (objects[0].clone(), objects[0].clone()),
+
+			2 => {
+				let mut objects = objects.into_iter();
+
+				(objects.next().unwrap(), objects.next().unwrap())
+			}
+
+			_ => {
+				let right_half = objects.split_off(objects.len() / 2);
+
+				(
+					Arc::new(Self::new(objects, time0, time1, rng)),
+					Arc::new(Self::new(right_half, time0, time1, rng))
+				)
+			}
+		};
+
+		let aabb = left.aabb(time0, time1).merge(right.aabb(time0, time1));
+
+		Self { left, right, aabb }
+	}
+}
+
+impl Object for BvhNode {
+	fn aabb(&self, _time0: f64, _time1: f64) -> Aabb {
+		self.aabb
+	}
+
+	fn hit(&self, ray: Ray, min_distance: f64, max_distance: f64) -> Option<Hit> {
+		if !self.aabb.hit(ray, min_distance, max_distance) {
+			return None;
+		}
+
+		match self.left.hit(ray, min_distance, max_distance) {
+			// The right subtree only needs to beat what the left subtree already found
+			Some(left_hit) => Some(
+				self.right
+					.hit(ray, min_distance, left_hit.distance)
+					.unwrap_or(left_hit)
+			),
+
+			None => self.right.hit(ray, min_distance, max_distance)
+		}
+	}
+}
+
+/// A leaf standing in for a `BvhNode` built from zero objects (an empty `Scene`); its AABB can
+/// never be hit, so it never needs to be matched against a real object
+struct EmptyObject;
+
+impl Object for EmptyObject {
+	fn aabb(&self, _time0: f64, _time1: f64) -> Aabb {
+		const INFINITY: f64 = std::f64::INFINITY;
+
+		Aabb {
+			min: Vec3(INFINITY, INFINITY, INFINITY),
+			max: Vec3(-INFINITY, -INFINITY, -INFINITY)
+		}
+	}
+
+	fn hit(&self, _ray: Ray, _min_distance: f64, _max_distance: f64) -> Option<Hit> {
+		None
+	}
 }
 
 pub struct Sphere {
@@ -72,7 +179,281 @@ impl Object for Sphere {
 		let point = ray.at(distance);
 		let normal = (point - center) / self.radius;
 
-		Some(Hit::new(ray, distance, point, normal, self.material.clone()))
+		let u = (-normal.2).atan2(normal.0) / (std::f64::consts::PI * 2.0) + 0.5;
+		let v = normal.1.asin() / std::f64::consts::PI + 0.5;
+
+		Some(Hit::new(ray, distance, point, normal, u, v, self.material.clone()))
+	}
+
+	fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f64 {
+		let ray = Ray { origin: origin, direction: direction, time: 0.0 };
+
+		if self.hit(ray, 0.001, std::f64::INFINITY).is_none() {
+			return 0.0;
+		}
+
+		let distance_squared = (self.center.at(0.0) - origin).dot(self.center.at(0.0) - origin);
+		let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+		let solid_angle = (1.0 - cos_theta_max) * std::f64::consts::PI * 2.0;
+
+		1.0 / solid_angle
+	}
+
+	fn random_toward(&self, origin: Vec3, rng: &mut dyn RngCore) -> Vec3 {
+		let direction = self.center.at(0.0) - origin;
+		let distance_squared = direction.dot(direction);
+
+		let r1 = rng.gen::<f64>();
+		let r2 = rng.gen::<f64>();
+
+		let z = 1.0 + r2 * ((1.0 - self.radius * self.radius / distance_squared).sqrt() - 1.0);
+		let phi = r1 * std::f64::consts::PI * 2.0;
+		let radius = (1.0 - z * z).sqrt();
+
+		Vec3(phi.cos() * radius, phi.sin() * radius, z).from_basis(direction.unit())
+	}
+}
+
+/// A rectangle in the plane "z = k", bounded by ["x0", "x1"] and ["y0", "y1"]
+pub struct XyRect {
+	pub x0: f64,
+	pub x1: f64,
+	pub y0: f64,
+	pub y1: f64,
+	pub k: f64,
+	pub material: Arc<dyn Material>
+}
+
+impl Object for XyRect {
+	fn aabb(&self, _time0: f64, _time1: f64) -> Aabb {
+		// A rectangle is infinitely thin, so we pad its AABB slightly along the degenerate axis
+		// to give the BVH a box with nonzero volume to bound it
+		const EPSILON: f64 = 0.0001;
+
+		Aabb {
+			min: Vec3(self.x0, self.y0, self.k - EPSILON),
+			max: Vec3(self.x1, self.y1, self.k + EPSILON)
+		}
+	}
+
+	fn hit(&self, ray: Ray, min_distance: f64, max_distance: f64) -> Option<Hit> {
+		let distance = (self.k - ray.origin.2) / ray.direction.2;
+
+		if distance < min_distance || distance >= max_distance {
+			return None;
+		}
+
+		let x = ray.origin.0 + ray.direction.0 * distance;
+		let y = ray.origin.1 + ray.direction.1 * distance;
+
+		if x < self.x0 || x > self.x1 || y < self.y0 || y > self.y1 {
+			return None;
+		}
+
+		let u = (x - self.x0) / (self.x1 - self.x0);
+		let v = (y - self.y0) / (self.y1 - self.y0);
+
+		Some(Hit::new(ray, distance, ray.at(distance), Vec3(0.0, 0.0, 1.0), u, v, self.material.clone()))
+	}
+
+	fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f64 {
+		match self.hit(Ray { origin: origin, direction: direction, time: 0.0 }, 0.001, std::f64::INFINITY) {
+			Some(hit) => {
+				let area = (self.x1 - self.x0) * (self.y1 - self.y0);
+				let distance_squared = hit.distance * hit.distance * direction.dot(direction);
+				let cosine = (direction.dot(hit.normal) / direction.len()).abs();
+
+				distance_squared / (cosine * area)
+			}
+
+			None => 0.0
+		}
+	}
+
+	fn random_toward(&self, origin: Vec3, rng: &mut dyn RngCore) -> Vec3 {
+		let point = Vec3(rng.gen_range(self.x0, self.x1), rng.gen_range(self.y0, self.y1), self.k);
+
+		point - origin
+	}
+}
+
+/// A rectangle in the plane "y = k", bounded by ["x0", "x1"] and ["z0", "z1"]
+pub struct XzRect {
+	pub x0: f64,
+	pub x1: f64,
+	pub z0: f64,
+	pub z1: f64,
+	pub k: f64,
+	pub material: Arc<dyn Material>
+}
+
+impl Object for XzRect {
+	fn aabb(&self, _time0: f64, _time1: f64) -> Aabb {
+		const EPSILON: f64 = 0.0001;
+
+		Aabb {
+			min: Vec3(self.x0, self.k - EPSILON, self.z0),
+			max: Vec3(self.x1, self.k + EPSILON, self.z1)
+		}
+	}
+
+	fn hit(&self, ray: Ray, min_distance: f64, max_distance: f64) -> Option<Hit> {
+		let distance = (self.k - ray.origin.1) / ray.direction.1;
+
+		if distance < min_distance || distance >= max_distance {
+			return None;
+		}
+
+		let x = ray.origin.0 + ray.direction.0 * distance;
+		let z = ray.origin.2 + ray.direction.2 * distance;
+
+		if x < self.x0 || x > self.x1 || z < self.z0 || z > self.z1 {
+			return None;
+		}
+
+		let u = (x - self.x0) / (self.x1 - self.x0);
+		let v = (z - self.z0) / (self.z1 - self.z0);
+
+		Some(Hit::new(ray, distance, ray.at(distance), Vec3(0.0, 1.0, 0.0), u, v, self.material.clone()))
+	}
+
+	fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f64 {
+		match self.hit(Ray { origin: origin, direction: direction, time: 0.0 }, 0.001, std::f64::INFINITY) {
+			Some(hit) => {
+				let area = (self.x1 - self.x0) * (self.z1 - self.z0);
+				let distance_squared = hit.distance * hit.distance * direction.dot(direction);
+				let cosine = (direction.dot(hit.normal) / direction.len()).abs();
+
+				distance_squared / (cosine * area)
+			}
+
+			None => 0.0
+		}
+	}
+
+	fn random_toward(&self, origin: Vec3, rng: &mut dyn RngCore) -> Vec3 {
+		let point = Vec3(rng.gen_range(self.x0, self.x1), self.k, rng.gen_range(self.z0, self.z1));
+
+		point - origin
+	}
+}
+
+/// A rectangle in the plane "x = k", bounded by ["y0", "y1"] and ["z0", "z1"]
+pub struct YzRect {
+	pub y0: f64,
+	pub y1: f64,
+	pub z0: f64,
+	pub z1: f64,
+	pub k: f64,
+	pub material: Arc<dyn Material>
+}
+
+impl Object for YzRect {
+	fn aabb(&self, _time0: f64, _time1: f64) -> Aabb {
+		const EPSILON: f64 = 0.0001;
+
+		Aabb {
+			min: Vec3(self.k - EPSILON, self.y0, self.z0),
+			max: Vec3(self.k + EPSILON, self.y1, self.z1)
+		}
+	}
+
+	fn hit(&self, ray: Ray, min_distance: f64, max_distance: f64) -> Option<Hit> {
+		let distance = (self.k - ray.origin.0) / ray.direction.0;
+
+		if distance < min_distance || distance >= max_distance {
+			return None;
+		}
+
+		let y = ray.origin.1 + ray.direction.1 * distance;
+		let z = ray.origin.2 + ray.direction.2 * distance;
+
+		if y < self.y0 || y > self.y1 || z < self.z0 || z > self.z1 {
+			return None;
+		}
+
+		let u = (y - self.y0) / (self.y1 - self.y0);
+		let v = (z - self.z0) / (self.z1 - self.z0);
+
+		Some(Hit::new(ray, distance, ray.at(distance), Vec3(1.0, 0.0, 0.0), u, v, self.material.clone()))
+	}
+
+	fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f64 {
+		match self.hit(Ray { origin: origin, direction: direction, time: 0.0 }, 0.001, std::f64::INFINITY) {
+			Some(hit) => {
+				let area = (self.y1 - self.y0) * (self.z1 - self.z0);
+				let distance_squared = hit.distance * hit.distance * direction.dot(direction);
+				let cosine = (direction.dot(hit.normal) / direction.len()).abs();
+
+				distance_squared / (cosine * area)
+			}
+
+			None => 0.0
+		}
+	}
+
+	fn random_toward(&self, origin: Vec3, rng: &mut dyn RngCore) -> Vec3 {
+		let point = Vec3(self.k, rng.gen_range(self.y0, self.y1), rng.gen_range(self.z0, self.z1));
+
+		point - origin
+	}
+}
+
+/// A closed, axis-aligned box composed of six rectangles
+pub struct Boxx {
+	min: Vec3,
+	max: Vec3,
+	sides: Vec<Arc<dyn Object>>
+}
+
+impl Boxx {
+	pub fn new(min: Vec3, max: Vec3, material: Arc<dyn Material>) -> Self {
+		let sides: Vec<Arc<dyn Object>> = vec![
+			Arc::new(XyRect {
+				x0: min.0, x1: max.0, y0: min.1, y1: max.1, k: min.2,
+				material: material.clone()
+			}),
+
+			Arc::new(XyRect {
+				x0: min.0, x1: max.0, y0: min.1, y1: max.1, k: max.2,
+				material: material.clone()
+			}),
+
+			Arc::new(XzRect {
+				x0: min.0, x1: max.0, z0: min.2, z1: max.2, k: min.1,
+				material: material.clone()
+			}),
+
+			Arc::new(XzRect {
+				x0: min.0, x1: max.0, z0: min.2, z1: max.2, k: max.1,
+				material: material.clone()
+			}),
+
+			Arc::new(YzRect {
+				y0: min.1, y1: max.1, z0: min.2, z1: max.2, k: min.0,
+				material: material.clone()
+			}),
+
+			Arc::new(YzRect {
+				y0: min.1, y1: max.1, z0: min.2, z1: max.2, k: max.0,
+				material: material
+			})
+		];
+
+		Self { min: min, max: max, sides: sides }
+	}
+}
+
+impl Object for Boxx {
+	fn aabb(&self, _time0: f64, _time1: f64) -> Aabb {
+		Aabb { min: self.min, max: self.max }
+	}
+
+	fn hit(&self, ray: Ray, min_distance: f64, max_distance: f64) -> Option<Hit> {
+		self.sides
+			.iter()
+			.filter_map(|side| side.hit(ray, min_distance, max_distance))
+			.min_by(|h1, h2| h1.distance.partial_cmp(&h2.distance).unwrap())
 	}
 }
 
@@ -81,6 +462,8 @@ pub struct Hit {
 	pub distance: f64,
 	pub point: Vec3,
 	pub normal: Vec3,
+	pub u: f64,
+	pub v: f64,
 	pub material: Arc<dyn Material>,
 	pub face: Face,
 }
@@ -91,6 +474,8 @@ impl Hit {
 		distance: f64,
 		point: Vec3,
 		normal: Vec3,
+		u: f64,
+		v: f64,
 		material: Arc<dyn Material>) -> Hit
 	{
 		let (normal, face) = if ray.direction.dot(normal) <= 0.0 {
@@ -104,6 +489,8 @@ impl Hit {
 			distance: distance,
 			point: point,
 			normal: normal,
+			u: u,
+			v: v,
 			material: material,
 			face: face
 		}
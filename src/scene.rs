@@ -1,51 +1,114 @@
 use crate::{
 	math::{Ray, Vec3},
-	object::Object
+	object::{BvhNode, Object}
 };
 
 use indicatif::{ProgressBar, ProgressStyle};
-use rand::Rng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_pcg::Pcg32;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
+use std::{
+	collections::hash_map::DefaultHasher,
+	hash::{Hash, Hasher},
+	sync::Arc
+};
+
+/// Builds a PRNG whose stream depends only on a pixel's coordinates and the render's seed, not
+/// on the order in which Rayon happens to schedule work, so the same "seed" always produces the
+/// same image
+fn pixel_rng(x: u32, y: u32, seed: u64) -> Pcg32 {
+	let mut hasher = DefaultHasher::new();
+
+	(x, y, seed).hash(&mut hasher);
+
+	Pcg32::seed_from_u64(hasher.finish())
+}
+
 pub struct Scene {
-	objects: Vec<Box<dyn Object>>
+	objects: Vec<Arc<dyn Object>>,
+	lights: Vec<Arc<dyn Object>>
 }
 
 impl Scene {
 	pub fn new() -> Self {
 		Self {
-			objects: Vec::new()
+			objects: Vec::new(),
+			lights: Vec::new()
 		}
 	}
 
 	pub fn add<T: Object + 'static>(&mut self, object: T) {
-		self.objects.push(Box::new(object));
+		self.objects.push(Arc::new(object));
+	}
+
+	/// Like "add", but also registers "object" as a light that's explicitly sampled at each
+	/// bounce via next-event estimation, which converges much faster than waiting for a ray to
+	/// stumble into it by chance
+	pub fn add_light<T: Object + 'static>(&mut self, object: T) {
+		let object: Arc<dyn Object> = Arc::new(object);
+
+		self.objects.push(object.clone());
+		self.lights.push(object);
 	}
 
-	fn color(&self, mut ray: Ray, min_distance: f64, max_distance: f64, max_depth: u32) -> Vec3 {
-		let mut color = Vec3(1.0, 1.0, 1.0);
+	fn color(&self,
+		root: &dyn Object,
+		mut ray: Ray,
+		min_distance: f64,
+		max_distance: f64,
+		max_depth: u32,
+		rng: &mut dyn RngCore) -> Vec3
+	{
+		let mut color = Vec3(0.0, 0.0, 0.0);
+		let mut throughput = Vec3(1.0, 1.0, 1.0);
 
 		for _ in 0..max_depth {
-			match self.objects
-				.iter()
-				.filter_map(|object| object.hit(ray, min_distance, max_distance))
-				.min_by(|h1, h2| h1.distance.partial_cmp(&h2.distance).unwrap())
-			{
+			match root.hit(ray, min_distance, max_distance) {
 				// If we hit the object
-				Some(hit) => match hit.material.redirect(&hit) {
-					// If the ray scattered
-					Some(redirection) => {
-						ray = Ray {
-							origin: hit.point,
-							direction: redirection.direction,
-							time: ray.time
-						};
-
-						color *= redirection.albedo;
+				Some(hit) => {
+					color += throughput * hit.material.emitted(&hit);
+
+					match hit.material.redirect(&hit, rng) {
+						// If the ray scattered
+						Some(mut redirection) => {
+							if !hit.material.is_specular() && !self.lights.is_empty() {
+								// Half the time, sample a direction toward a random light instead
+								// of the material's own distribution
+								if rng.gen::<bool>() {
+									let light = &self.lights[rng.gen_range(0, self.lights.len())];
+
+									redirection.direction = light.random_toward(hit.point, rng).unit();
+									redirection.pdf = hit.material.pdf(&hit, redirection.direction);
+								}
+
+								let light_pdf = self.lights
+									.iter()
+									.map(|light| light.pdf_value(hit.point, redirection.direction))
+									.sum::<f64>() / self.lights.len() as f64;
+
+								let mixture_pdf = redirection.pdf * 0.5 + light_pdf * 0.5;
+
+								// Avoid dividing by (near) zero when both PDFs are vanishingly small
+								if mixture_pdf < 1e-8 {
+									break;
+								}
+
+								throughput *= redirection.albedo * (redirection.pdf / mixture_pdf);
+							} else {
+								throughput *= redirection.albedo;
+							}
+
+							ray = Ray {
+								origin: hit.point,
+								direction: redirection.direction,
+								time: ray.time
+							};
+						}
+
+						// If the object absorbed the ray
+						None => break
 					}
-
-					// If the object absorbed the ray
-					None => break
 				}
 
 				// If we didn't hit the object
@@ -55,12 +118,12 @@ impl Scene {
 					const COLOR1: Vec3 = Vec3(0.5, 0.7, 1.0);
 					const COLOR2: Vec3 = Vec3(1.0, 1.0, 1.0);
 
-					return color * (COLOR1 * t + COLOR2 * (1.0 - t));
+					return color + throughput * (COLOR1 * t + COLOR2 * (1.0 - t));
 				}
 			}
 		}
 
-		Vec3(0.0, 0.0, 0.0)
+		color
 	}
 
 	pub fn render(&self,
@@ -70,8 +133,14 @@ impl Scene {
 		min_distance: f64,
 		max_distance: f64,
 		samples_per_pixel: u32,
-		max_depth: u32) -> Vec<Vec3>
+		max_depth: u32,
+		seed: u64) -> Vec<Vec3>
 	{
+		// Distinct from "pixel_rng"'s stream so the tree's shape depends on the seed alone, not on
+		// the image's dimensions
+		let mut bvh_rng = Pcg32::seed_from_u64(seed);
+		let root = &BvhNode::new(self.objects.clone(), camera.time0, camera.time1, &mut bvh_rng);
+
 		let pb = &ProgressBar::new((width * height) as u64);
 
 		pb.set_draw_delta(width as u64);
@@ -82,13 +151,15 @@ impl Scene {
 
 		let image = (0..height).into_par_iter().flat_map(|y| {
 			(0..width).into_par_iter().map(move |x| {
-				let color = (0..samples_per_pixel).into_par_iter().map(|_| {
-					let mut rng = rand::thread_rng();
+				// A fresh PRNG per pixel keeps each pixel's sample sequence fixed regardless of
+				// how Rayon schedules the surrounding work
+				let mut rng = pixel_rng(x, y, seed);
 
+				let color = (0..samples_per_pixel).map(|_| {
 					let u = (x as f64 + rng.gen::<f64>()) / width as f64;
 					let v = (y as f64 + rng.gen::<f64>()) / height as f64;
 
-					self.color(camera.ray(u, v), min_distance, max_distance, max_depth)
+					self.color(root, camera.ray(u, v, &mut rng), min_distance, max_distance, max_depth, &mut rng)
 				}).sum::<Vec3>() / samples_per_pixel as f64;
 
 				pb.inc(1);
@@ -174,8 +245,8 @@ impl Camera {
 		}
 	}
 
-	fn ray(&self, u: f64, v: f64) -> Ray {
-		let w = Vec3::random_in_unit_disk() * self.lens_radius;
+	fn ray(&self, u: f64, v: f64, rng: &mut impl Rng) -> Ray {
+		let w = Vec3::random_in_unit_disk(rng) * self.lens_radius;
 		let offset = self.horizontal_unit * w.0 + self.vertical_unit * w.1;
 
 		Ray {
@@ -185,7 +256,7 @@ impl Camera {
 			time: if self.time0 == self.time1 {
 				self.time0
 			} else {
-				rand::thread_rng().gen_range(self.time0, self.time1)
+				rng.gen_range(self.time0, self.time1)
 			}
 		}
 	}
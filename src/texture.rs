@@ -0,0 +1,148 @@
+use crate::math::Vec3;
+
+use rand::Rng;
+
+use std::sync::Arc;
+
+pub trait Texture: Send + Sync {
+	fn value(&self, u: f64, v: f64, point: Vec3) -> Vec3;
+}
+
+pub struct SolidColor(pub Vec3);
+
+impl Texture for SolidColor {
+	fn value(&self, _u: f64, _v: f64, _point: Vec3) -> Vec3 {
+		self.0
+	}
+}
+
+pub struct Checker {
+	pub even: Arc<dyn Texture>,
+	pub odd: Arc<dyn Texture>,
+	pub scale: f64
+}
+
+impl Texture for Checker {
+	fn value(&self, u: f64, v: f64, point: Vec3) -> Vec3 {
+		let sign = (self.scale * point.0).sin() * (self.scale * point.1).sin() * (self.scale * point.2).sin();
+
+		if sign > 0.0 {
+			self.even.value(u, v, point)
+		} else {
+			self.odd.value(u, v, point)
+		}
+	}
+}
+
+/// Perlin noise turned into marble-like veins, following the classic "summed sine plus
+/// turbulence" trick rather than raw noise, since raw noise alone looks like static
+pub struct Perlin {
+	random: Vec<Vec3>,
+	perm_x: Vec<usize>,
+	perm_y: Vec<usize>,
+	perm_z: Vec<usize>,
+	pub scale: f64
+}
+
+impl Perlin {
+	const POINT_COUNT: usize = 256;
+
+	pub fn new(rng: &mut impl Rng, scale: f64) -> Self {
+		let random = (0..Self::POINT_COUNT)
+			.map(|_| Vec3::random_in_cube(rng, -1.0, 1.0).unit())
+			.collect();
+
+		Self {
+			random: random,
+			perm_x: Self::generate_perm(rng),
+			perm_y: Self::generate_perm(rng),
+			perm_z: Self::generate_perm(rng),
+			scale: scale
+		}
+	}
+
+	fn generate_perm(rng: &mut impl Rng) -> Vec<usize> {
+		let mut values: Vec<usize> = (0..Self::POINT_COUNT).collect();
+
+		for i in (1..Self::POINT_COUNT).rev() {
+			values.swap(i, rng.gen_range(0, i + 1));
+		}
+
+		values
+	}
+
+	fn noise(&self, point: Vec3) -> f64 {
+		let u = point.0 - point.0.floor();
+		let v = point.1 - point.1.floor();
+		let w = point.2 - point.2.floor();
+
+		let i = point.0.floor() as i32;
+		let j = point.1.floor() as i32;
+		let k = point.2.floor() as i32;
+
+		let mut corners = [[[Vec3(0.0, 0.0, 0.0); 2]; 2]; 2];
+
+		for di in 0..2i32 {
+			for dj in 0..2i32 {
+				for dk in 0..2i32 {
+					let index = self.perm_x[((i + di) & 255) as usize]
+						^ self.perm_y[((j + dj) & 255) as usize]
+						^ self.perm_z[((k + dk) & 255) as usize];
+
+					corners[di as usize][dj as usize][dk as usize] = self.random[index];
+				}
+			}
+		}
+
+		Self::interpolate(corners, u, v, w)
+	}
+
+	fn interpolate(corners: [[[Vec3; 2]; 2]; 2], u: f64, v: f64, w: f64) -> f64 {
+		// Hermite smoothing to avoid the blocky look of a naive trilinear blend
+		let uu = u * u * (3.0 - 2.0 * u);
+		let vv = v * v * (3.0 - 2.0 * v);
+		let ww = w * w * (3.0 - 2.0 * w);
+
+		let mut accum = 0.0;
+
+		for i in 0..2 {
+			for j in 0..2 {
+				for k in 0..2 {
+					let weight = Vec3(u - i as f64, v - j as f64, w - k as f64);
+
+					accum +=
+						(i as f64 * uu + (1 - i) as f64 * (1.0 - uu)) *
+						(j as f64 * vv + (1 - j) as f64 * (1.0 - vv)) *
+						(k as f64 * ww + (1 - k) as f64 * (1.0 - ww)) *
+						corners[i][j][k].dot(weight);
+				}
+			}
+		}
+
+		accum
+	}
+
+	fn turbulence(&self, point: Vec3, depth: u32) -> f64 {
+		let mut accum = 0.0;
+		let mut point = point;
+		let mut weight = 1.0;
+
+		for _ in 0..depth {
+			accum += weight * self.noise(point);
+			weight *= 0.5;
+			point *= 2.0;
+		}
+
+		accum.abs()
+	}
+}
+
+impl Texture for Perlin {
+	fn value(&self, _u: f64, _v: f64, point: Vec3) -> Vec3 {
+		const TURBULENCE_DEPTH: u32 = 7;
+
+		let marble = (self.scale * point.2 + self.turbulence(point, TURBULENCE_DEPTH) * 10.0).sin() * 0.5 + 0.5;
+
+		Vec3(1.0, 1.0, 1.0) * marble
+	}
+}